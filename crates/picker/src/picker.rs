@@ -149,6 +149,11 @@ pub trait PickerDelegate: Sized + 'static {
         None
     }
     fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Picker<Self>>);
+    /// Override to have clicking the entry at `ix` only select it instead of also confirming it.
+    /// Called before the clicked entry is selected.
+    fn confirm_on_click(&self, _ix: usize, _cx: &App) -> bool {
+        true
+    }
     /// Instead of interacting with currently selected entry, treats editor input literally,
     /// performing some kind of action on it.
     fn confirm_input(
@@ -578,8 +583,11 @@ impl<D: PickerDelegate> Picker<D> {
     ) {
         cx.stop_propagation();
         window.prevent_default();
+        let confirm = self.delegate.confirm_on_click(ix, cx);
         self.set_selected_index(ix, None, false, window, cx);
-        self.do_confirm(secondary, window, cx)
+        if confirm {
+            self.do_confirm(secondary, window, cx)
+        }
     }
 
     fn do_confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Self>) {
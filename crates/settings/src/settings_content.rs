@@ -59,6 +59,8 @@ pub struct SettingsContent {
 
     pub tabs: Option<ItemSettingsContent>,
     pub tab_bar: Option<TabBarSettingsContent>,
+    /// Settings related to the tab switcher.
+    pub tab_switcher: Option<TabSwitcherSettingsContent>,
     pub status_bar: Option<StatusBarSettingsContent>,
 
     pub preview_tabs: Option<PreviewTabsSettingsContent>,
@@ -663,6 +665,60 @@ pub enum FileFinderWidthContent {
     Full,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct TabSwitcherSettingsContent {
+    /// Whether tabs for files closer to the worktree root should rank higher
+    /// than equally good matches for more deeply nested files.
+    ///
+    /// Default: false
+    pub prefer_shallow: Option<bool>,
+    /// How much each directory level lowers a match's score when `prefer_shallow` is enabled.
+    ///
+    /// Default: 0.05
+    #[schemars(range(min = 0.0))]
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub depth_penalty: Option<f32>,
+    /// What clicking a tab in the tab switcher does.
+    ///
+    /// Default: confirm_on_click
+    pub mouse_behavior: Option<TabSwitcherMouseBehavior>,
+    /// Whether every tab shows its close button, rather than only the selected
+    /// tab and the tab under the mouse.
+    ///
+    /// Default: false
+    pub always_show_close: Option<bool>,
+    /// Whether `tab_switcher::Toggle` lists the tabs of the active pane and of the
+    /// panes directly beside it in the same split, rather than only the active
+    /// pane's tabs. Panes inside a nested split are not included.
+    ///
+    /// Default: false
+    pub scope_to_group: Option<bool>,
+}
+
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TabSwitcherMouseBehavior {
+    /// Clicking a tab switches to it right away.
+    #[default]
+    ConfirmOnClick,
+    /// Clicking a tab selects it, and clicking the selected tab switches to it.
+    SelectThenConfirm,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, Debug, JsonSchema, MergeFrom)]
 pub struct VimSettingsContent {
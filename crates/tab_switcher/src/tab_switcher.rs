@@ -1,34 +1,38 @@
+pub mod tab_switcher_settings;
 #[cfg(test)]
 mod tab_switcher_tests;
 
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use editor::items::{
     entry_diagnostic_aware_icon_decoration_and_color, entry_git_aware_label_color,
 };
 use fuzzy::StringMatchCandidate;
 use gpui::{
     Action, AnyElement, App, Context, DismissEvent, Entity, EntityId, EventEmitter, FocusHandle,
-    Focusable, Modifiers, ModifiersChangedEvent, MouseButton, MouseUpEvent, ParentElement, Point,
-    Render, Styled, Task, WeakEntity, Window, actions, rems,
+    Focusable, Global, Modifiers, ModifiersChangedEvent, MouseButton, MouseUpEvent, ParentElement,
+    Point, PromptLevel, Render, Styled, Task, WeakEntity, Window, actions, rems,
 };
 use picker::{Picker, PickerDelegate};
 use project::Project;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use settings::Settings;
-use std::{cmp::Reverse, sync::Arc};
+use std::{cell::RefCell, cmp::Reverse, mem, rc::Rc, sync::Arc, time::Duration};
+use tab_switcher_settings::{TabSwitcherMouseBehavior, TabSwitcherSettings};
 use ui::{
     DecoratedIcon, IconDecoration, IconDecorationKind, ListItem, ListItemSpacing, Tooltip,
     prelude::*,
 };
 use util::ResultExt;
 use workspace::{
-    ModalView, Pane, SaveIntent, Workspace,
+    Member, ModalView, Pane, SaveIntent, SplitDirection, Workspace,
     item::{ItemHandle, ItemSettings, ShowDiagnostics, TabContentParams},
     pane::{Event as PaneEvent, render_item_indicator, tab_details},
 };
 
 const PANEL_WIDTH_REMS: f32 = 28.;
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(50);
+const CLOSE_ALL_CONFIRMATION_THRESHOLD: usize = 5;
 
 /// Toggles the tab switcher interface.
 #[derive(PartialEq, Clone, Deserialize, JsonSchema, Default, Action)]
@@ -43,11 +47,47 @@ actions!(
     [
         /// Closes the selected item in the tab switcher.
         CloseSelectedItem,
+        /// Closes every item currently listed in the tab switcher.
+        CloseAllMatching,
+        /// Pins every item currently listed in the tab switcher.
+        PinAllMatching,
+        /// Switches to the selected item and closes the item that was active
+        /// when the tab switcher was opened.
+        ReplaceCurrentWith,
+        /// Switches to the selected item and pins it.
+        ConfirmAndPin,
+        /// Switches to the selected item and opens the item that was active when
+        /// the tab switcher was opened in a split beside it.
+        OpenWithPrevious,
+        /// Switches back to the item that was active when the tab switcher was
+        /// last opened and confirmed.
+        ReturnToOrigin,
         /// Toggles between showing all tabs or just the current pane's tabs.
-        ToggleAll
+        ToggleAll,
+        /// Turns previewing the selected item while navigating the tab switcher
+        /// on or off for the rest of the session.
+        TogglePreviews
     ]
 );
 
+/// Set by [`TogglePreviews`]; not persisted across restarts.
+#[derive(Default)]
+struct PreviewsDisabled(bool);
+
+impl Global for PreviewsDisabled {}
+
+/// The item that was active when the tab switcher was last opened in a workspace.
+#[derive(Clone)]
+struct Origin {
+    pane: WeakEntity<Pane>,
+    item_id: EntityId,
+}
+
+fn previews_enabled(cx: &App) -> bool {
+    !cx.try_global::<PreviewsDisabled>()
+        .is_some_and(|disabled| disabled.0)
+}
+
 pub struct TabSwitcher {
     picker: Entity<Picker<TabSwitcherDelegate>>,
     init_modifiers: Option<Modifiers>,
@@ -65,36 +105,80 @@ impl TabSwitcher {
         _window: Option<&mut Window>,
         _: &mut Context<Workspace>,
     ) {
-        workspace.register_action(|workspace, action: &Toggle, window, cx| {
-            let Some(tab_switcher) = workspace.active_modal::<Self>(cx) else {
-                Self::open(workspace, action.select_last, false, window, cx);
-                return;
-            };
+        let last_origin = Rc::new(RefCell::new(None));
+        workspace.register_action({
+            let last_origin = last_origin.clone();
+            move |workspace, action: &Toggle, window, cx| {
+                let Some(tab_switcher) = workspace.active_modal::<Self>(cx) else {
+                    Self::open(
+                        workspace,
+                        action.select_last,
+                        false,
+                        last_origin.clone(),
+                        window,
+                        cx,
+                    );
+                    return;
+                };
 
-            tab_switcher.update(cx, |tab_switcher, cx| {
-                tab_switcher
-                    .picker
-                    .update(cx, |picker, cx| picker.cycle_selection(window, cx))
-            });
+                tab_switcher.update(cx, |tab_switcher, cx| {
+                    tab_switcher.cycle_selection(window, cx)
+                });
+            }
+        });
+        workspace.register_action({
+            let last_origin = last_origin.clone();
+            move |workspace, _action: &ToggleAll, window, cx| {
+                let Some(tab_switcher) = workspace.active_modal::<Self>(cx) else {
+                    Self::open(workspace, false, true, last_origin.clone(), window, cx);
+                    return;
+                };
+
+                tab_switcher.update(cx, |tab_switcher, cx| {
+                    tab_switcher.cycle_selection(window, cx)
+                });
+            }
         });
-        workspace.register_action(|workspace, _action: &ToggleAll, window, cx| {
-            let Some(tab_switcher) = workspace.active_modal::<Self>(cx) else {
-                Self::open(workspace, false, true, window, cx);
+        workspace.register_action(move |_, _action: &ReturnToOrigin, window, cx| {
+            // Unlike going back in the navigation history, this ignores any navigation
+            // that happened after the tab switcher was used.
+            let Some(origin) = last_origin.borrow().clone() else {
                 return;
             };
-
-            tab_switcher.update(cx, |tab_switcher, cx| {
-                tab_switcher
-                    .picker
-                    .update(cx, |picker, cx| picker.cycle_selection(window, cx))
+            let Some(pane) = origin.pane.upgrade() else {
+                return;
+            };
+            pane.update(cx, |pane, cx| {
+                if let Some(index) = pane
+                    .items()
+                    .position(|item| item.item_id() == origin.item_id)
+                {
+                    pane.activate_item(index, true, true, window, cx);
+                }
             });
         });
+        workspace.register_action(|workspace, _action: &TogglePreviews, _window, cx| {
+            let previews_disabled = cx.default_global::<PreviewsDisabled>();
+            previews_disabled.0 = !previews_disabled.0;
+            let previews_disabled = previews_disabled.0;
+            if let Some(tab_switcher) = workspace.active_modal::<Self>(cx) {
+                tab_switcher.update(cx, |tab_switcher, cx| {
+                    tab_switcher.picker.update(cx, |picker, cx| {
+                        if previews_disabled {
+                            picker.delegate.pending_preview = None;
+                        }
+                        cx.notify();
+                    })
+                });
+            }
+        });
     }
 
     fn open(
         workspace: &mut Workspace,
         select_last: bool,
         is_global: bool,
+        last_origin: Rc<RefCell<Option<Origin>>>,
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
@@ -117,6 +201,10 @@ impl TabSwitcher {
             })
         }
 
+        let scope_panes = (!is_global && TabSwitcherSettings::get_global(cx).scope_to_group)
+            .then(|| split_panes(&workspace.center().root, weak_pane.entity_id()))
+            .flatten()
+            .unwrap_or_else(|| vec![weak_pane.clone()]);
         let weak_workspace = workspace.weak_handle();
 
         let project = workspace.project().clone();
@@ -131,8 +219,10 @@ impl TabSwitcher {
                 select_last,
                 cx.entity().downgrade(),
                 weak_pane,
+                scope_panes,
                 weak_workspace,
                 is_global,
+                last_origin,
                 window,
                 cx,
                 original_items,
@@ -164,6 +254,18 @@ impl TabSwitcher {
         }
     }
 
+    fn cycle_selection(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            // Matches for all panes are only computed on the next frame, so a toggle that
+            // arrives before then is applied once they are in instead of being lost.
+            if picker.delegate.awaiting_initial_matches {
+                picker.delegate.pending_cycles += 1;
+            } else {
+                picker.cycle_selection(window, cx);
+            }
+        });
+    }
+
     fn handle_modifiers_changed(
         &mut self,
         event: &ModifiersChangedEvent,
@@ -195,6 +297,59 @@ impl TabSwitcher {
                 .close_item_at(picker.delegate.selected_index(), window, cx)
         });
     }
+
+    fn handle_replace_current_with(
+        &mut self,
+        _: &ReplaceCurrentWith,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.replace_origin_with_selected(window, cx)
+        });
+    }
+
+    fn handle_confirm_and_pin(
+        &mut self,
+        _: &ConfirmAndPin,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker
+            .update(cx, |picker, cx| picker.delegate.confirm_and_pin(window, cx));
+    }
+
+    fn handle_pin_all_matching(
+        &mut self,
+        _: &PinAllMatching,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker
+            .update(cx, |picker, cx| picker.delegate.pin_all_matches(window, cx));
+    }
+
+    fn handle_open_with_previous(
+        &mut self,
+        _: &OpenWithPrevious,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.open_selected_beside_origin(window, cx)
+        });
+    }
+
+    fn handle_close_all_matching(
+        &mut self,
+        _: &CloseAllMatching,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.picker.update(cx, |picker, cx| {
+            picker.delegate.close_all_matches(window, cx)
+        });
+    }
 }
 
 impl EventEmitter<DismissEvent> for TabSwitcher {}
@@ -212,6 +367,11 @@ impl Render for TabSwitcher {
             .w(rems(PANEL_WIDTH_REMS))
             .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
             .on_action(cx.listener(Self::handle_close_selected_item))
+            .on_action(cx.listener(Self::handle_close_all_matching))
+            .on_action(cx.listener(Self::handle_pin_all_matching))
+            .on_action(cx.listener(Self::handle_replace_current_with))
+            .on_action(cx.listener(Self::handle_confirm_and_pin))
+            .on_action(cx.listener(Self::handle_open_with_previous))
             .child(self.picker.clone())
     }
 }
@@ -230,12 +390,20 @@ pub struct TabSwitcherDelegate {
     tab_switcher: WeakEntity<TabSwitcher>,
     selected_index: usize,
     pane: WeakEntity<Pane>,
+    /// The panes whose tabs are listed when not showing all panes.
+    scope_panes: Vec<WeakEntity<Pane>>,
+    origin_item_id: Option<EntityId>,
+    /// Shared with the workspace's [`ReturnToOrigin`] handler.
+    last_origin: Rc<RefCell<Option<Origin>>>,
     workspace: WeakEntity<Workspace>,
     project: Entity<Project>,
     matches: Vec<TabMatch>,
     original_items: Vec<(Entity<Pane>, usize)>,
     is_all_panes: bool,
     restored_items: bool,
+    pending_preview: Option<Task<()>>,
+    awaiting_initial_matches: bool,
+    pending_cycles: usize,
 }
 
 impl TabMatch {
@@ -307,6 +475,58 @@ impl TabMatch {
                 });
         Some(DecoratedIcon::new(colored_icon, decorations))
     }
+
+    fn path_depth(&self, cx: &App) -> usize {
+        self.item.project_path(cx).map_or(0, |project_path| {
+            project_path.path.components().count().saturating_sub(1)
+        })
+    }
+
+    /// Items are free to render anything as their tab content, so when an item has no
+    /// tab text we label its row with its path (or its id) to keep it identifiable.
+    fn fallback_label(&self, project: &Entity<Project>, cx: &App) -> Option<SharedString> {
+        let tab_text = self.item.tab_content_text(self.detail, cx);
+        if !tab_text.trim().is_empty() {
+            return None;
+        }
+        let path_style = project.read(cx).path_style(cx);
+        let label = self
+            .item
+            .project_path(cx)
+            .map(|project_path| project_path.path.display(path_style).into_owned())
+            .filter(|path| !path.is_empty())
+            .unwrap_or_else(|| format!("Item {}", self.item.item_id().as_u64()));
+        Some(label.into())
+    }
+}
+
+/// Returns the pane with `pane_id` along with the panes directly beside it in the innermost
+/// split containing it, or `None` if that pane isn't part of `member`. Panes in nested splits
+/// belong to their own group, so a pane at the top of a layout doesn't pick up every pane.
+fn split_panes(member: &Member, pane_id: EntityId) -> Option<Vec<WeakEntity<Pane>>> {
+    let Member::Axis(axis) = member else {
+        return None;
+    };
+    let sibling_panes = axis
+        .members
+        .iter()
+        .filter_map(|member| match member {
+            Member::Pane(pane) => Some(pane),
+            Member::Axis(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if !sibling_panes.iter().any(|pane| pane.entity_id() == pane_id) {
+        return axis
+            .members
+            .iter()
+            .find_map(|member| split_panes(member, pane_id));
+    }
+    Some(
+        sibling_panes
+            .into_iter()
+            .map(|pane| pane.downgrade())
+            .collect(),
+    )
 }
 
 impl TabSwitcherDelegate {
@@ -316,24 +536,42 @@ impl TabSwitcherDelegate {
         select_last: bool,
         tab_switcher: WeakEntity<TabSwitcher>,
         pane: WeakEntity<Pane>,
+        scope_panes: Vec<WeakEntity<Pane>>,
         workspace: WeakEntity<Workspace>,
         is_all_panes: bool,
+        last_origin: Rc<RefCell<Option<Origin>>>,
         window: &mut Window,
         cx: &mut Context<TabSwitcher>,
         original_items: Vec<(Entity<Pane>, usize)>,
     ) -> Self {
-        Self::subscribe_to_updates(&pane, window, cx);
+        for pane in &scope_panes {
+            Self::subscribe_to_updates(pane, window, cx);
+        }
+        let origin_item_id = pane
+            .upgrade()
+            .and_then(|pane| pane.read(cx).active_item())
+            .map(|item| item.item_id());
+        *last_origin.borrow_mut() = origin_item_id.map(|item_id| Origin {
+            pane: pane.clone(),
+            item_id,
+        });
         Self {
             select_last,
             tab_switcher,
             selected_index: 0,
             pane,
+            scope_panes,
+            origin_item_id,
+            last_origin,
             workspace,
             project,
             matches: Vec::new(),
             is_all_panes,
             original_items,
             restored_items: false,
+            pending_preview: None,
+            awaiting_initial_matches: is_all_panes,
+            pending_cycles: 0,
         }
     }
 
@@ -407,7 +645,7 @@ impl TabSwitcherDelegate {
                     ))
                 })
                 .collect::<Vec<_>>();
-            smol::block_on(fuzzy::match_strings(
+            let mut string_matches = smol::block_on(fuzzy::match_strings(
                 &candidates,
                 &query,
                 true,
@@ -415,10 +653,32 @@ impl TabSwitcherDelegate {
                 10000,
                 &Default::default(),
                 cx.background_executor().clone(),
-            ))
-            .into_iter()
-            .map(|m| all_items[m.candidate_id].clone())
-            .collect()
+            ));
+            let settings = TabSwitcherSettings::get_global(cx);
+            if settings.prefer_shallow {
+                let depth_penalty = settings.depth_penalty as f64;
+                let mut scored_matches = string_matches
+                    .into_iter()
+                    .map(|string_match| {
+                        let depth = all_items[string_match.candidate_id].path_depth(cx);
+                        (
+                            string_match.score - depth_penalty * depth as f64,
+                            string_match,
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                // The sort is stable, so matches with equal adjusted scores keep the
+                // fuzzy matcher's order.
+                scored_matches.sort_by(|(a_score, _), (b_score, _)| b_score.total_cmp(a_score));
+                string_matches = scored_matches
+                    .into_iter()
+                    .map(|(_, string_match)| string_match)
+                    .collect();
+            }
+            string_matches
+                .into_iter()
+                .map(|m| all_items[m.candidate_id].clone())
+                .collect()
         };
 
         let selected_item_id = self.selected_item_id();
@@ -439,37 +699,60 @@ impl TabSwitcherDelegate {
             window.defer(cx, move |window, cx| {
                 this.update(cx, |this, cx| {
                     this.delegate.update_all_pane_matches(query, window, cx);
+                    if mem::take(&mut this.delegate.awaiting_initial_matches) {
+                        let pending_cycles = mem::take(&mut this.delegate.pending_cycles);
+                        if this.delegate.match_count() > 0 {
+                            for _ in 0..pending_cycles {
+                                this.cycle_selection(window, cx);
+                            }
+                        }
+                    }
                 })
             });
             return;
         }
         let selected_item_id = self.selected_item_id();
         self.matches.clear();
-        let Some(pane) = self.pane.upgrade() else {
+        let panes = self
+            .scope_panes
+            .iter()
+            .filter_map(|pane| pane.upgrade())
+            .collect::<Vec<_>>();
+        if panes.is_empty() {
             return;
-        };
+        }
 
-        let pane = pane.read(cx);
+        // Activation timestamps are shared by all panes, so the histories of several panes
+        // can be merged into one.
+        let mut history_entries = panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).activation_history())
+            .collect::<Vec<_>>();
+        history_entries.sort_by_key(|history_entry| Reverse(history_entry.timestamp));
         let mut history_indices = HashMap::default();
-        pane.activation_history().iter().rev().enumerate().for_each(
-            |(history_index, history_entry)| {
+        history_entries
+            .into_iter()
+            .enumerate()
+            .for_each(|(history_index, history_entry)| {
                 history_indices.insert(history_entry.entity_id, history_index);
-            },
-        );
+            });
 
-        let items: Vec<Box<dyn ItemHandle>> = pane.items().map(|item| item.boxed_clone()).collect();
-        items
-            .iter()
-            .enumerate()
-            .zip(tab_details(&items, window, cx))
-            .map(|((item_index, item), detail)| TabMatch {
-                pane: self.pane.clone(),
-                item_index,
-                item: item.boxed_clone(),
-                detail,
-                preview: pane.is_active_preview_item(item.item_id()),
-            })
-            .for_each(|tab_match| self.matches.push(tab_match));
+        let mut item_index = 0;
+        for pane_handle in &panes {
+            let pane = pane_handle.read(cx);
+            let items: Vec<Box<dyn ItemHandle>> =
+                pane.items().map(|item| item.boxed_clone()).collect();
+            for (item, detail) in items.iter().zip(tab_details(&items, window, cx)) {
+                self.matches.push(TabMatch {
+                    pane: pane_handle.downgrade(),
+                    item_index,
+                    item: item.boxed_clone(),
+                    detail,
+                    preview: pane.is_active_preview_item(item.item_id()),
+                });
+                item_index += 1;
+            }
+        }
 
         let non_history_base = history_indices.len();
         self.matches.sort_by(move |a, b| {
@@ -545,6 +828,224 @@ impl TabSwitcherDelegate {
                 .detach_and_log_err(cx);
         });
     }
+
+    fn replace_origin_with_selected(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let Some(selected_match) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let selected_item_id = selected_match.item.item_id();
+        let origin_item_id = self
+            .origin_item_id
+            .filter(|origin_item_id| *origin_item_id != selected_item_id);
+
+        self.confirm(false, window, cx);
+        if let Some(origin_item_id) = origin_item_id
+            && let Some(pane) = self.pane.upgrade()
+        {
+            pane.update(cx, |pane, cx| {
+                pane.close_item_by_id(origin_item_id, SaveIntent::Close, window, cx)
+                    .detach_and_log_err(cx);
+            });
+        }
+    }
+
+    fn confirm_and_pin(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let Some(selected_match) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let selected_item_id = selected_match.item.item_id();
+        let pane = selected_match.pane.clone();
+
+        self.confirm(false, window, cx);
+        pane.update(cx, |pane, cx| {
+            if let Some(ix) = pane
+                .items()
+                .position(|item| item.item_id() == selected_item_id)
+                && ix >= pane.pinned_count()
+            {
+                pane.pin_tab_at(ix, window, cx);
+            }
+        })
+        .ok();
+    }
+
+    fn open_selected_beside_origin(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let Some(selected_match) = self.matches.get(self.selected_index()) else {
+            return;
+        };
+        let selected_item_id = selected_match.item.item_id();
+        let selected_path = selected_match.item.project_path(cx);
+        let target_pane = selected_match.pane.clone();
+        let origin_item = self
+            .origin_item_id
+            .filter(|origin_item_id| *origin_item_id != selected_item_id)
+            .zip(self.pane.upgrade())
+            .and_then(|(origin_item_id, pane)| {
+                pane.read(cx)
+                    .items()
+                    .find(|item| item.item_id() == origin_item_id)
+                    .map(|item| item.boxed_clone())
+            })
+            .filter(|item| item.can_split(cx))
+            // Another view of the same file is already beside the selection.
+            .filter(|item| selected_path.is_none() || item.project_path(cx) != selected_path);
+
+        self.confirm(false, window, cx);
+        let Some(origin_item) = origin_item else {
+            return;
+        };
+        let Some(workspace_id) = self
+            .workspace
+            .read_with(cx, |workspace, _| workspace.database_id())
+            .ok()
+        else {
+            return;
+        };
+        let origin_clone = origin_item.clone_on_split(workspace_id, window, cx);
+        let workspace = self.workspace.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            let Some(origin_clone) = origin_clone.await else {
+                return anyhow::Ok(());
+            };
+            workspace.update_in(cx, |workspace, window, cx| {
+                let Some(target_pane) = target_pane.upgrade() else {
+                    return;
+                };
+                let new_pane = workspace.split_pane(
+                    target_pane.clone(),
+                    SplitDirection::vertical(cx),
+                    window,
+                    cx,
+                );
+                workspace.add_item(new_pane, origin_clone, None, false, false, window, cx);
+                target_pane.update(cx, |pane, cx| pane.focus_active_item(window, cx));
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Whether the row at `ix` shows its close button without being hovered.
+    fn close_button_always_visible(&self, ix: usize, cx: &App) -> bool {
+        ix == self.selected_index || TabSwitcherSettings::get_global(cx).always_show_close
+    }
+
+    /// Groups the listed items that aren't pinned by the pane they belong to. Like the
+    /// pane's own bulk actions, batch operations leave pinned tabs alone.
+    fn unpinned_matches_by_pane(&self, cx: &App) -> Vec<(WeakEntity<Pane>, HashSet<EntityId>)> {
+        let mut items_by_pane: Vec<(WeakEntity<Pane>, HashSet<EntityId>)> = Vec::new();
+        for tab_match in &self.matches {
+            let item_id = tab_match.item.item_id();
+            if let Some((_, item_ids)) = items_by_pane
+                .iter_mut()
+                .find(|(pane, _)| pane.entity_id() == tab_match.pane.entity_id())
+            {
+                item_ids.insert(item_id);
+            } else {
+                items_by_pane.push((tab_match.pane.clone(), HashSet::from_iter([item_id])));
+            }
+        }
+        items_by_pane.retain_mut(|(pane, item_ids)| {
+            let Some(pane) = pane.upgrade() else {
+                return false;
+            };
+            for pinned_item_id in pane.read(cx).pinned_item_ids() {
+                item_ids.remove(&pinned_item_id);
+            }
+            !item_ids.is_empty()
+        });
+        items_by_pane
+    }
+
+    fn pin_all_matches(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let items_by_pane = self.unpinned_matches_by_pane(cx);
+        if items_by_pane.is_empty() {
+            return;
+        }
+
+        cx.spawn_in(window, async move |_, cx| {
+            for (pane, item_ids) in items_by_pane {
+                // Pin in tab order so the pinned tabs keep their relative order. Pinning moves
+                // the tab on the next effect cycle, so look its index up again for each one.
+                let ordered_item_ids = pane.read_with(cx, |pane, _| {
+                    pane.items()
+                        .map(|item| item.item_id())
+                        .filter(|item_id| item_ids.contains(item_id))
+                        .collect::<Vec<_>>()
+                })?;
+                for item_id in ordered_item_ids {
+                    pane.update_in(cx, |pane, window, cx| {
+                        if let Some(ix) = pane.items().position(|item| item.item_id() == item_id)
+                            && ix >= pane.pinned_count()
+                        {
+                            pane.pin_tab_at(ix, window, cx);
+                        }
+                    })?;
+                }
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn close_all_matches(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Picker<TabSwitcherDelegate>>,
+    ) {
+        let items_by_pane = self.unpinned_matches_by_pane(cx);
+        if items_by_pane.is_empty() {
+            return;
+        }
+
+        let item_count = items_by_pane
+            .iter()
+            .map(|(_, item_ids)| item_ids.len())
+            .sum::<usize>();
+        let answer = (item_count > CLOSE_ALL_CONFIRMATION_THRESHOLD).then(|| {
+            window.prompt(
+                PromptLevel::Warning,
+                &format!("Close {item_count} tabs?"),
+                None,
+                &["Close", "Cancel"],
+                cx,
+            )
+        });
+        cx.spawn_in(window, async move |_, cx| {
+            if let Some(answer) = answer
+                && answer.await != Ok(0)
+            {
+                return anyhow::Ok(());
+            }
+            // Close pane by pane so that each pane batches the save prompts for its
+            // dirty items instead of asking about every item separately.
+            for (pane, item_ids) in items_by_pane {
+                pane.update_in(cx, |pane, window, cx| {
+                    pane.close_items(window, cx, SaveIntent::Close, move |item_id| {
+                        item_ids.contains(&item_id)
+                    })
+                })?
+                .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
 }
 
 impl PickerDelegate for TabSwitcherDelegate {
@@ -573,19 +1074,30 @@ impl PickerDelegate for TabSwitcherDelegate {
         cx: &mut Context<Picker<Self>>,
     ) {
         self.selected_index = ix;
+        cx.notify();
 
-        let Some(selected_match) = self.matches.get(self.selected_index()) else {
+        let Some(selected_match) = self
+            .matches
+            .get(self.selected_index())
+            .filter(|_| previews_enabled(cx))
+        else {
+            self.pending_preview = None;
             return;
         };
-        selected_match
-            .pane
-            .update(cx, |pane, cx| {
-                if let Some(index) = pane.index_for_item(selected_match.item.as_ref()) {
+        // Cycling quickly through the list shouldn't activate every intermediate item,
+        // so only preview the selection once it has settled. Replacing the task cancels
+        // any preview that is still pending.
+        let pane = selected_match.pane.clone();
+        let item = selected_match.item.boxed_clone();
+        self.pending_preview = Some(cx.spawn_in(window, async move |_, cx| {
+            cx.background_executor().timer(PREVIEW_DEBOUNCE).await;
+            pane.update_in(cx, |pane, window, cx| {
+                if let Some(index) = pane.index_for_item(item.as_ref()) {
                     pane.activate_item(index, false, false, window, cx);
                 }
             })
             .ok();
-        cx.notify();
+        }));
     }
 
     fn separators_after_indices(&self) -> Vec<usize> {
@@ -612,6 +1124,7 @@ impl PickerDelegate for TabSwitcherDelegate {
             return;
         };
 
+        self.pending_preview = None;
         self.restored_items = true;
         for (pane, index) in self.original_items.iter() {
             pane.update(cx, |this, cx| {
@@ -628,8 +1141,18 @@ impl PickerDelegate for TabSwitcherDelegate {
             .ok();
     }
 
+    fn confirm_on_click(&self, ix: usize, cx: &App) -> bool {
+        match TabSwitcherSettings::get_global(cx).mouse_behavior {
+            TabSwitcherMouseBehavior::ConfirmOnClick => true,
+            TabSwitcherMouseBehavior::SelectThenConfirm => ix == self.selected_index,
+        }
+    }
+
     fn dismissed(&mut self, window: &mut Window, cx: &mut Context<Picker<TabSwitcherDelegate>>) {
+        self.pending_preview = None;
         if !self.restored_items {
+            // Nothing was switched to, so there is nothing to return from.
+            self.last_origin.borrow_mut().take();
             for (pane, index) in self.original_items.iter() {
                 pane.update(cx, |this, cx| {
                     this.activate_item(*index, false, false, window, cx);
@@ -642,6 +1165,28 @@ impl PickerDelegate for TabSwitcherDelegate {
             .log_err();
     }
 
+    fn render_footer(&self, _: &mut Window, cx: &mut Context<Picker<Self>>) -> Option<AnyElement> {
+        if previews_enabled(cx) {
+            return None;
+        }
+
+        Some(
+            h_flex()
+                .w_full()
+                .px_2()
+                .py_1()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .debug_selector(|| "TAB_SWITCHER_PREVIEWS_OFF".into())
+                .child(
+                    Label::new("Previews off")
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .into_any_element(),
+        )
+    }
+
     fn render_match(
         &self,
         ix: usize,
@@ -657,9 +1202,26 @@ impl PickerDelegate for TabSwitcherDelegate {
             preview: tab_match.preview,
             deemphasized: false,
         };
-        let label = tab_match.item.tab_content(params, window, cx);
-
-        let icon = tab_match.icon(&self.project, selected, window, cx);
+        let fallback_label = tab_match.fallback_label(&self.project, cx);
+        let icon = tab_match
+            .icon(&self.project, selected, window, cx)
+            .or_else(|| {
+                fallback_label.is_some().then(|| {
+                    DecoratedIcon::new(Icon::new(IconName::FileGeneric).color(Color::Muted), None)
+                })
+            });
+        let label_container = h_flex().w_full().debug_selector(|| {
+            let text = fallback_label
+                .clone()
+                .unwrap_or_else(|| tab_match.item.tab_content_text(tab_match.detail, cx));
+            format!("TAB_SWITCHER_ROW-{text}")
+        });
+        let label = match fallback_label {
+            Some(fallback_label) => Label::new(fallback_label)
+                .color(params.text_color())
+                .into_any_element(),
+            None => tab_match.item.tab_content(params, window, cx),
+        };
 
         let indicator = render_item_indicator(tab_match.item.boxed_clone(), cx);
         let indicator_color = if let Some(ref indicator) = indicator {
@@ -674,6 +1236,7 @@ impl PickerDelegate for TabSwitcherDelegate {
             .into_any_element();
         let close_button = div()
             .id("close-button")
+            .debug_selector(|| format!("TAB_SWITCHER_CLOSE-{ix}"))
             .on_mouse_up(
                 // We need this on_mouse_up here because on macOS you may have ctrl held
                 // down to open the menu, and a ctrl-click comes through as a right click.
@@ -700,14 +1263,21 @@ impl PickerDelegate for TabSwitcherDelegate {
                 .spacing(ListItemSpacing::Sparse)
                 .inset(true)
                 .toggle_state(selected)
-                .child(h_flex().w_full().child(label))
+                .child(label_container.child(label))
                 .start_slot::<DecoratedIcon>(icon)
                 .map(|el| {
-                    if self.selected_index == ix {
-                        el.end_slot::<AnyElement>(close_button)
-                    } else {
+                    if !self.close_button_always_visible(ix, cx) {
                         el.end_slot::<AnyElement>(indicator)
                             .end_hover_slot::<AnyElement>(close_button)
+                    } else if self.selected_index == ix {
+                        el.end_slot::<AnyElement>(close_button)
+                    } else {
+                        el.end_slot::<AnyElement>(
+                            h_flex()
+                                .child(indicator)
+                                .child(close_button)
+                                .into_any_element(),
+                        )
                     }
                 }),
         )
@@ -0,0 +1,26 @@
+pub use settings::TabSwitcherMouseBehavior;
+use settings::{RegisterSetting, Settings};
+
+#[derive(Debug, Clone, Copy, PartialEq, RegisterSetting)]
+pub struct TabSwitcherSettings {
+    pub prefer_shallow: bool,
+    pub depth_penalty: f32,
+    pub mouse_behavior: TabSwitcherMouseBehavior,
+    pub always_show_close: bool,
+    pub scope_to_group: bool,
+}
+
+impl Settings for TabSwitcherSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let tab_switcher = content.tab_switcher.as_ref().unwrap();
+
+        Self {
+            prefer_shallow: tab_switcher.prefer_shallow.unwrap(),
+            // A negative penalty would rank deeper files first instead.
+            depth_penalty: tab_switcher.depth_penalty.unwrap().max(0.0),
+            mouse_behavior: tab_switcher.mouse_behavior.unwrap(),
+            always_show_close: tab_switcher.always_show_close.unwrap(),
+            scope_to_group: tab_switcher.scope_to_group.unwrap(),
+        }
+    }
+}
@@ -4,8 +4,14 @@ use gpui::{TestAppContext, VisualTestContext};
 use menu::SelectPrevious;
 use project::{Project, ProjectPath};
 use serde_json::json;
-use util::{path, rel_path::rel_path};
-use workspace::{AppState, Workspace};
+use settings::SettingsStore;
+use std::{cell::Cell, rc::Rc};
+use util::{path, paths::PathStyle, rel_path::rel_path};
+use workspace::{
+    AppState, SplitDirection, Workspace,
+    item::test::{TestItem, TestProjectItem},
+    pane::TogglePinTab,
+};
 
 #[ctor::ctor]
 fn init_logger() {
@@ -280,6 +286,954 @@ async fn test_close_preserves_selected_position(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_rapid_cycling_previews_only_settled_selection(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+                "4.txt": "Fourth file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+    let tab_4 = open_buffer("4.txt", &workspace, cx).await;
+
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    let activation_count = Rc::new(Cell::new(0));
+    let _subscription = cx.update(|_, cx| {
+        let activation_count = activation_count.clone();
+        cx.subscribe(&pane, move |_, event: &PaneEvent, _| {
+            if let PaneEvent::ActivateItem { .. } = event {
+                activation_count.set(activation_count.get() + 1);
+            }
+        })
+    });
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(Toggle { select_last: false });
+    cx.dispatch_action(Toggle { select_last: false });
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 3, tab_1.boxed_clone());
+    });
+
+    // The highlight moves immediately, but nothing is activated while cycling.
+    assert_eq!(activation_count.get(), 0);
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.active_item().map(|item| item.item_id()),
+            Some(tab_4.item_id())
+        );
+    });
+
+    cx.executor().advance_clock(PREVIEW_DEBOUNCE);
+    cx.run_until_parked();
+    assert_eq!(activation_count.get(), 1);
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.active_item().map(|item| item.item_id()),
+            Some(tab_1.item_id())
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_toggle_previews(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    let activation_count = Rc::new(Cell::new(0));
+    let _subscription = cx.update(|_, cx| {
+        let activation_count = activation_count.clone();
+        cx.subscribe(&pane, move |_, event: &PaneEvent, _| {
+            if let PaneEvent::ActivateItem { .. } = event {
+                activation_count.set(activation_count.get() + 1);
+            }
+        })
+    });
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    cx.run_until_parked();
+    assert!(cx.debug_bounds("TAB_SWITCHER_PREVIEWS_OFF").is_none());
+    cx.dispatch_action(TogglePreviews);
+    cx.dispatch_action(Toggle { select_last: false });
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 2, tab_1.boxed_clone());
+    });
+    cx.run_until_parked();
+    assert!(
+        cx.debug_bounds("TAB_SWITCHER_PREVIEWS_OFF").is_some(),
+        "the footer doesn't show that previews are off"
+    );
+
+    // With previews off, moving the selection only changes the highlight.
+    cx.executor().advance_clock(PREVIEW_DEBOUNCE);
+    cx.run_until_parked();
+    assert_eq!(activation_count.get(), 0);
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.active_item().map(|item| item.item_id()),
+            Some(tab_3.item_id())
+        );
+    });
+
+    cx.dispatch_action(menu::Confirm);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(
+            pane.active_item().map(|item| item.item_id()),
+            Some(tab_1.item_id())
+        );
+    });
+
+    // Previews stay off when the tab switcher is opened again, until toggled back on.
+    activation_count.set(0);
+    open_tab_switcher(false, &workspace, cx);
+    cx.executor().advance_clock(PREVIEW_DEBOUNCE);
+    cx.run_until_parked();
+    assert_eq!(activation_count.get(), 0);
+
+    assert!(cx.debug_bounds("TAB_SWITCHER_PREVIEWS_OFF").is_some());
+
+    cx.dispatch_action(TogglePreviews);
+    cx.dispatch_action(Toggle { select_last: false });
+    cx.executor().advance_clock(PREVIEW_DEBOUNCE);
+    cx.run_until_parked();
+    assert_eq!(activation_count.get(), 1);
+    assert!(cx.debug_bounds("TAB_SWITCHER_PREVIEWS_OFF").is_none());
+}
+
+#[gpui::test]
+async fn test_close_all_matching(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "alpha.txt": "First file",
+                "also.txt": "Second file",
+                "beta.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    open_buffer("alpha.txt", &workspace, cx).await;
+    open_buffer("also.txt", &workspace, cx).await;
+    let beta = open_buffer("beta.txt", &workspace, cx).await;
+
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        tab_switcher.set_query("al", window, cx);
+    });
+    cx.run_until_parked();
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+    });
+
+    cx.dispatch_action(CloseAllMatching);
+    assert!(!cx.has_pending_prompt());
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    pane.read_with(cx, |pane, _| {
+        let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+        assert_eq!(item_ids, vec![beta.item_id()]);
+    });
+}
+
+#[gpui::test]
+async fn test_close_all_matching_keeps_pinned_tabs(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "alpha.txt": "First file",
+                "also.txt": "Second file",
+                "beta.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let alpha = open_buffer("alpha.txt", &workspace, cx).await;
+    cx.dispatch_action(TogglePinTab);
+    cx.run_until_parked();
+    open_buffer("also.txt", &workspace, cx).await;
+    let beta = open_buffer("beta.txt", &workspace, cx).await;
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    pane.read_with(cx, |pane, _| assert_eq!(pane.pinned_count(), 1));
+
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        tab_switcher.set_query("al", window, cx);
+    });
+    cx.run_until_parked();
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+    });
+
+    cx.dispatch_action(CloseAllMatching);
+    cx.run_until_parked();
+    pane.read_with(cx, |pane, _| {
+        let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+        assert_eq!(item_ids, vec![alpha.item_id(), beta.item_id()]);
+    });
+}
+
+#[gpui::test]
+async fn test_pin_all_matching(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "alpha.txt": "First file",
+                "also.txt": "Second file",
+                "beta.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let beta = open_buffer("beta.txt", &workspace, cx).await;
+    let alpha = open_buffer("alpha.txt", &workspace, cx).await;
+    let also = open_buffer("also.txt", &workspace, cx).await;
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        tab_switcher.set_query("al", window, cx);
+    });
+    cx.run_until_parked();
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+    });
+
+    cx.dispatch_action(PinAllMatching);
+    cx.run_until_parked();
+    pane.read_with(cx, |pane, _| {
+        assert_eq!(pane.pinned_count(), 2);
+        let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+        assert_eq!(
+            item_ids,
+            vec![alpha.item_id(), also.item_id(), beta.item_id()]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_close_all_matching_confirms_large_batches(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "",
+                "2.txt": "",
+                "3.txt": "",
+                "4.txt": "",
+                "5.txt": "",
+                "6.txt": "",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    for file_path in ["1.txt", "2.txt", "3.txt", "4.txt", "5.txt", "6.txt"] {
+        open_buffer(file_path, &workspace, cx).await;
+    }
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 6);
+    });
+
+    cx.dispatch_action(CloseAllMatching);
+    assert!(cx.has_pending_prompt());
+    cx.simulate_prompt_answer("Cancel");
+    cx.run_until_parked();
+    pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 6));
+
+    cx.dispatch_action(CloseAllMatching);
+    cx.simulate_prompt_answer("Close");
+    cx.run_until_parked();
+    pane.read_with(cx, |pane, _| assert_eq!(pane.items_len(), 0));
+}
+
+#[gpui::test]
+async fn test_fallback_label_for_items_without_tab_content(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(path!("/root"), json!({"1.txt": "First file"}))
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let stub_with_path = cx.new(|cx| {
+        TestItem::new(cx).with_project_items(&[TestProjectItem::new(100, "src/stub.txt", cx)])
+    });
+    let stub_without_path = cx.new(TestItem::new);
+    workspace.update_in(cx, |workspace, window, cx| {
+        workspace.add_item_to_active_pane(Box::new(stub_with_path.clone()), None, true, window, cx);
+        workspace.add_item_to_active_pane(
+            Box::new(stub_without_path.clone()),
+            None,
+            true,
+            window,
+            cx,
+        );
+    });
+
+    let stub_path = rel_path("src/stub.txt")
+        .display(PathStyle::local())
+        .into_owned();
+    let stub_id_label = format!("Item {}", stub_without_path.entity_id().as_u64());
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 3);
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+        let labels = tab_switcher
+            .delegate
+            .matches
+            .iter()
+            .map(|tab_match| tab_match.fallback_label(&tab_switcher.delegate.project, cx))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                Some(stub_id_label.clone().into()),
+                Some(stub_path.clone().into()),
+                None,
+            ]
+        );
+    });
+
+    // The rows are rendered with their fallback labels.
+    cx.run_until_parked();
+    for label in [stub_id_label, stub_path, "1.txt".to_string()] {
+        let selector: &'static str = format!("TAB_SWITCHER_ROW-{label}").leak();
+        assert!(
+            cx.debug_bounds(selector).is_some(),
+            "no row is labeled {label:?}"
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_toggle_twice_before_matches_are_ready(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+
+    // Both toggles are handled before the all-panes matches have been computed.
+    toggle_twice_and_assert_single_tab_switcher(ToggleAll.boxed_clone(), &workspace, cx);
+    cx.run_until_parked();
+
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 3);
+        assert_match_selection(tab_switcher, 2, tab_1.boxed_clone());
+    });
+
+    cx.dispatch_action(menu::Cancel);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+
+    toggle_twice_and_assert_single_tab_switcher(
+        Toggle { select_last: false }.boxed_clone(),
+        &workspace,
+        cx,
+    );
+    cx.run_until_parked();
+
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 3);
+        assert_match_selection(tab_switcher, 2, tab_1.boxed_clone());
+    });
+}
+
+#[gpui::test]
+async fn test_prefer_shallow_matches(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "lib.rs": "",
+                "crates": {
+                    "core": {
+                        "lib.rs": "",
+                    },
+                },
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let shallow = open_buffer("lib.rs", &workspace, cx).await;
+    let nested = open_buffer("crates/core/lib.rs", &workspace, cx).await;
+
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        tab_switcher.set_query("lib", window, cx);
+    });
+    cx.run_until_parked();
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+        assert_match_at_position(tab_switcher, 0, nested.boxed_clone());
+        assert_match_at_position(tab_switcher, 1, shallow.boxed_clone());
+    });
+    cx.dispatch_action(menu::Cancel);
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.tab_switcher.get_or_insert_default().prefer_shallow = Some(true);
+            });
+        });
+    });
+
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        tab_switcher.set_query("lib", window, cx);
+    });
+    cx.run_until_parked();
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 2);
+        assert_match_at_position(tab_switcher, 0, shallow.boxed_clone());
+        assert_match_at_position(tab_switcher, 1, nested.boxed_clone());
+    });
+
+    // A negative penalty would rank deeper files first, so it is ignored.
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.tab_switcher.get_or_insert_default().depth_penalty = Some(-1.0);
+            });
+        });
+        assert_eq!(TabSwitcherSettings::get_global(cx).depth_penalty, 0.0);
+    });
+}
+
+#[gpui::test]
+async fn test_mouse_behavior(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+
+    // By default, a click switches to the clicked tab right away.
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_at_position(tab_switcher, 2, tab_1.boxed_clone());
+    });
+    click_row("1.txt", cx);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "1.txt");
+    });
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.tab_switcher.get_or_insert_default().mouse_behavior =
+                    Some(TabSwitcherMouseBehavior::SelectThenConfirm);
+            });
+        });
+    });
+
+    // The first click only selects the clicked tab, clicking it again switches to it.
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_at_position(tab_switcher, 2, tab_2.boxed_clone());
+    });
+    click_row("2.txt", cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 2, tab_2.boxed_clone());
+    });
+    cx.read(|cx| {
+        let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "1.txt");
+    });
+
+    click_row("2.txt", cx);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "2.txt");
+    });
+}
+
+#[gpui::test]
+async fn test_always_show_close(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    open_buffer("1.txt", &workspace, cx).await;
+    open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        assert_eq!(tab_switcher.delegate.selected_index(), 1);
+        assert!(!tab_switcher.delegate.close_button_always_visible(0, cx));
+        assert!(tab_switcher.delegate.close_button_always_visible(1, cx));
+        assert!(!tab_switcher.delegate.close_button_always_visible(2, cx));
+    });
+    cx.run_until_parked();
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-0").is_none());
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-1").is_some());
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-2").is_none());
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings
+                    .tab_switcher
+                    .get_or_insert_default()
+                    .always_show_close = Some(true);
+            });
+        });
+    });
+    tab_switcher.update(cx, |tab_switcher, cx| {
+        assert!(tab_switcher.delegate.close_button_always_visible(0, cx));
+        assert!(tab_switcher.delegate.close_button_always_visible(1, cx));
+        assert!(tab_switcher.delegate.close_button_always_visible(2, cx));
+        cx.notify();
+    });
+    cx.run_until_parked();
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-0").is_some());
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-1").is_some());
+    assert!(cx.debug_bounds("TAB_SWITCHER_CLOSE-2").is_some());
+}
+
+#[gpui::test]
+async fn test_replace_current_with_selected(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    open_buffer("3.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+    });
+
+    cx.dispatch_action(ReplaceCurrentWith);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "2.txt");
+        let item_ids = workspace
+            .read(cx)
+            .active_pane()
+            .read(cx)
+            .items()
+            .map(|item| item.item_id())
+            .collect::<Vec<_>>();
+        assert_eq!(item_ids, vec![tab_1.item_id(), tab_2.item_id()]);
+    });
+}
+
+#[gpui::test]
+async fn test_confirm_and_pin(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+    });
+
+    cx.dispatch_action(ConfirmAndPin);
+    cx.run_until_parked();
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let active_editor = workspace.read(cx).active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "2.txt");
+        let pane = workspace.read(cx).active_pane().read(cx);
+        assert_eq!(pane.pinned_count(), 1);
+        let item_ids = pane.items().map(|item| item.item_id()).collect::<Vec<_>>();
+        assert_eq!(
+            item_ids,
+            vec![tab_2.item_id(), tab_1.item_id(), tab_3.item_id()]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_return_to_origin(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    let tab_1 = open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    let activate = |item: &dyn ItemHandle, cx: &mut VisualTestContext| {
+        pane.update_in(cx, |pane, window, cx| {
+            let index = pane.index_for_item(item).unwrap();
+            pane.activate_item(index, true, true, window, cx);
+        });
+    };
+    let active_item_id = |cx: &mut VisualTestContext| {
+        pane.read_with(cx, |pane, _| pane.active_item().map(|item| item.item_id()))
+    };
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+    });
+    cx.dispatch_action(menu::Confirm);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    assert_eq!(active_item_id(cx), Some(tab_2.item_id()));
+
+    // Navigating elsewhere afterwards doesn't change where we return to.
+    activate(tab_1.as_ref(), cx);
+    cx.dispatch_action(ReturnToOrigin);
+    assert_eq!(active_item_id(cx), Some(tab_3.item_id()));
+
+    // Dismissing the tab switcher without switching forgets the origin.
+    open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(menu::Cancel);
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    activate(tab_1.as_ref(), cx);
+    cx.dispatch_action(ReturnToOrigin);
+    assert_eq!(active_item_id(cx), Some(tab_1.item_id()));
+}
+
+#[gpui::test]
+async fn test_open_with_previous(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            path!("/root"),
+            json!({
+                "1.txt": "First file",
+                "2.txt": "Second file",
+                "3.txt": "Third file",
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), [path!("/root").as_ref()], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    open_buffer("1.txt", &workspace, cx).await;
+    let tab_2 = open_buffer("2.txt", &workspace, cx).await;
+    let tab_3 = open_buffer("3.txt", &workspace, cx).await;
+    let pane = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+
+    // Selecting the tab that was already active opens it without a split.
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    cx.dispatch_action(SelectPrevious);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 0, tab_3.boxed_clone());
+    });
+    cx.dispatch_action(OpenWithPrevious);
+    cx.run_until_parked();
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    workspace.read_with(cx, |workspace, _| assert_eq!(workspace.panes().len(), 1));
+
+    let tab_switcher = open_tab_switcher(false, &workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_match_selection(tab_switcher, 1, tab_2.boxed_clone());
+    });
+    cx.dispatch_action(OpenWithPrevious);
+    cx.run_until_parked();
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let workspace = workspace.read(cx);
+        assert_eq!(workspace.panes().len(), 2);
+        assert_eq!(workspace.active_pane(), &pane);
+        let active_editor = workspace.active_item_as::<Editor>(cx).unwrap();
+        assert_eq!(active_editor.read(cx).title(cx), "2.txt");
+        assert_eq!(pane.read(cx).items_len(), 3);
+
+        let split_pane = workspace.panes().iter().find(|p| **p != pane).unwrap();
+        let split_editor = split_pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<Editor>())
+            .unwrap();
+        assert_eq!(split_editor.read(cx).title(cx), "3.txt");
+    });
+
+    // Selecting another view of the origin's file switches to it without a split.
+    let split_pane = workspace.read_with(cx, |workspace, _| {
+        workspace
+            .panes()
+            .iter()
+            .find(|p| **p != pane)
+            .unwrap()
+            .clone()
+    });
+    split_pane.update_in(cx, |pane, window, cx| pane.focus_active_item(window, cx));
+    cx.dispatch_action(ToggleAll);
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update_in(cx, |tab_switcher, window, cx| {
+        let ix = tab_switcher
+            .delegate
+            .matches
+            .iter()
+            .position(|tab_match| tab_match.item.item_id() == tab_3.item_id())
+            .unwrap();
+        tab_switcher.set_selected_index(ix, None, false, window, cx);
+    });
+    cx.dispatch_action(OpenWithPrevious);
+    cx.run_until_parked();
+    assert_tab_switcher_is_closed(workspace.clone(), cx);
+    cx.read(|cx| {
+        let workspace = workspace.read(cx);
+        assert_eq!(workspace.panes().len(), 2);
+        assert_eq!(workspace.active_pane(), &pane);
+        assert_eq!(
+            workspace.active_item(cx).map(|item| item.item_id()),
+            Some(tab_3.item_id())
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_scope_to_group(cx: &mut gpui::TestAppContext) {
+    let app_state = init_test(cx);
+    let project = Project::test(app_state.fs.clone(), [], cx).await;
+    let (workspace, cx) =
+        cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+    // A | D | (B / C)
+    let pane_a = workspace.read_with(cx, |workspace, _| workspace.active_pane().clone());
+    let (pane_b, pane_c, pane_d) = workspace.update_in(cx, |workspace, window, cx| {
+        let pane_b = workspace.split_pane(pane_a.clone(), SplitDirection::Right, window, cx);
+        let pane_c = workspace.split_pane(pane_b.clone(), SplitDirection::Down, window, cx);
+        let pane_d = workspace.split_pane(pane_a.clone(), SplitDirection::Right, window, cx);
+        (pane_b, pane_c, pane_d)
+    });
+    let item_a = add_test_item(&pane_a, cx);
+    let item_d = add_test_item(&pane_d, cx);
+    let item_c = add_test_item(&pane_c, cx);
+    let item_b = add_test_item(&pane_b, cx);
+    cx.run_until_parked();
+
+    let listed_item_ids = |cx: &mut VisualTestContext| {
+        let tab_switcher = open_tab_switcher(false, &workspace, cx);
+        let item_ids = tab_switcher.read_with(cx, |tab_switcher, _| {
+            tab_switcher
+                .delegate
+                .matches
+                .iter()
+                .map(|tab_match| tab_match.item.item_id())
+                .collect::<HashSet<_>>()
+        });
+        cx.dispatch_action(menu::Cancel);
+        item_ids
+    };
+
+    assert_eq!(listed_item_ids(cx), HashSet::from_iter([item_b.item_id()]));
+
+    cx.update(|_, cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.tab_switcher.get_or_insert_default().scope_to_group = Some(true);
+            });
+        });
+    });
+    assert_eq!(
+        listed_item_ids(cx),
+        HashSet::from_iter([item_b.item_id(), item_c.item_id()])
+    );
+
+    // The nested split next to A and D is a group of its own.
+    pane_a.update_in(cx, |pane, window, cx| window.focus(&pane.focus_handle(cx)));
+    cx.run_until_parked();
+    assert_eq!(
+        listed_item_ids(cx),
+        HashSet::from_iter([item_a.item_id(), item_d.item_id()])
+    );
+
+    // ToggleAll isn't affected by the setting.
+    cx.dispatch_action(ToggleAll);
+    cx.run_until_parked();
+    let tab_switcher = get_active_tab_switcher(&workspace, cx);
+    tab_switcher.update(cx, |tab_switcher, _| {
+        assert_eq!(tab_switcher.delegate.matches.len(), 4);
+    });
+}
+
 fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
     cx.update(|cx| {
         let state = AppState::test(cx);
@@ -300,6 +1254,27 @@ fn open_tab_switcher(
     get_active_tab_switcher(workspace, cx)
 }
 
+#[track_caller]
+fn toggle_twice_and_assert_single_tab_switcher(
+    action: Box<dyn Action>,
+    workspace: &Entity<Workspace>,
+    cx: &mut VisualTestContext,
+) {
+    cx.update(|window, cx| {
+        let active_tab_switcher_id = |cx: &App| {
+            workspace
+                .read(cx)
+                .active_modal::<TabSwitcher>(cx)
+                .map(|tab_switcher| tab_switcher.entity_id())
+        };
+        window.dispatch_action(action.boxed_clone(), cx);
+        let first_tab_switcher_id = active_tab_switcher_id(cx);
+        assert!(first_tab_switcher_id.is_some(), "tab switcher is not open");
+        window.dispatch_action(action, cx);
+        assert_eq!(active_tab_switcher_id(cx), first_tab_switcher_id);
+    });
+}
+
 #[track_caller]
 fn get_active_tab_switcher(
     workspace: &Entity<Workspace>,
@@ -315,6 +1290,24 @@ fn get_active_tab_switcher(
     })
 }
 
+fn click_row(label: &str, cx: &mut VisualTestContext) {
+    cx.run_until_parked();
+    let selector: &'static str = format!("TAB_SWITCHER_ROW-{label}").leak();
+    let bounds = cx
+        .debug_bounds(selector)
+        .unwrap_or_else(|| panic!("no row is labeled {label:?}"));
+    cx.simulate_click(bounds.center(), Modifiers::none());
+    cx.run_until_parked();
+}
+
+fn add_test_item(pane: &Entity<Pane>, cx: &mut VisualTestContext) -> Box<dyn ItemHandle> {
+    let item: Box<dyn ItemHandle> = Box::new(cx.new(TestItem::new));
+    pane.update_in(cx, |pane, window, cx| {
+        pane.add_item(item.boxed_clone(), true, true, None, window, cx);
+    });
+    item
+}
+
 async fn open_buffer(
     file_path: &str,
     workspace: &Entity<Workspace>,
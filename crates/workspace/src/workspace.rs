@@ -4331,6 +4331,10 @@ impl Workspace {
         &self.panes
     }
 
+    pub fn center(&self) -> &PaneGroup {
+        &self.center
+    }
+
     pub fn active_pane(&self) -> &Entity<Pane> {
         &self.active_pane
     }